@@ -3,30 +3,36 @@
 use {
     super::*,
     crate::pack::{Packable, Unpackable},
-    reclutch_event::{self as event, prelude::*},
-    std::{any::Any, collections::HashMap, sync::Arc},
+    alloc::{boxed::Box, sync::Arc, vec::Vec},
+    core::any::Any,
+    reclutch_event as event,
 };
 
 type QueueEvent<Id> = Event<Id, Arc<dyn any::Any + Send + Sync>>;
 
+type Handler<T> = Arc<dyn Fn(<T as Packable>::Packed, &dyn Any) -> Propagation + Send + Sync>;
+
+/// A priority-ordered bucket of handlers registered for a single `(Id, TypeId)` or wildcard `TypeId`.
+type HandlerBucket<T> = Vec<(i32, HandlerId, Handler<T>)>;
+
 /// An adapter over an underlying listener in which a list of handlers are dispatched based on event type and ID.
 ///
 /// This will not dispatch automatically. [`dispatch`](Listener::dispatch) must be called at regular intervals to handle events.
 ///
 /// This type cannot be constructed directly. Invoke the `listen` method on the corresponding queue to create a new `Listener`.
-pub struct Listener<Id: Clone + std::hash::Hash + Eq, T: Packable> {
-    handlers:
-        HashMap<(Id, any::TypeId), Arc<dyn Fn(<T as Packable>::Packed, &dyn Any) + Send + Sync>>,
+pub struct Listener<Id: Clone + core::hash::Hash + Eq, T: Packable> {
+    handlers: HashMap<(Id, any::TypeId), HandlerBucket<T>>,
+    any_handlers: HashMap<any::TypeId, HandlerBucket<T>>,
     listener: event::ts::Listener<QueueEvent<Id>>,
 }
 
-impl<Id: Clone + std::hash::Hash + Eq, T: Packable> Listener<Id, T> {
+impl<Id: Clone + core::hash::Hash + Eq, T: Packable> Listener<Id, T> {
     /// Adds a handler to `self` and returns `Self`.
     ///
     /// `id` marks the source ID. The type of the third parameter of the handler is the event type.
     /// Both of these will be used to match correct events.
     ///
-    /// If the ID and event type are already being handled, the handler will be replaced.
+    /// Equivalent to [`and_on_with_priority`](Listener::and_on_with_priority) with a priority of `0`.
     pub fn and_on<'a, E: Send + Sync + 'static, P: 'a>(
         mut self,
         id: Id,
@@ -39,62 +45,408 @@ impl<Id: Clone + std::hash::Hash + Eq, T: Packable> Listener<Id, T> {
         self
     }
 
+    /// Adds a handler to `self` and returns `Self`.
+    ///
+    /// Handlers with a higher `priority` are invoked first during [`dispatch`](Listener::dispatch). Handlers
+    /// of equal priority are invoked in the order they were added.
+    pub fn and_on_with_priority<'a, E: Send + Sync + 'static, P: 'a>(
+        mut self,
+        id: Id,
+        priority: i32,
+        handler: impl Fn(P, &E) + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_with_priority(id, priority, handler);
+        self
+    }
+
     /// Adds a handler.
     ///
     /// `id` marks the source ID. The type of the third parameter of the handler is the event type.
     /// Both of these will be used to match correct events.
     ///
-    /// If the ID and event type are already being handled, the handler will be replaced.
+    /// Multiple handlers may be registered for the same `id` and event type; all of them are invoked
+    /// during [`dispatch`](Listener::dispatch). Returns a [`HandlerId`] which can be passed to
+    /// [`remove`](Listener::remove) to remove this specific handler.
+    ///
+    /// Equivalent to [`on_with_priority`](Listener::on_with_priority) with a priority of `0`.
     pub fn on<'a, E: Send + Sync + 'static, P: 'a>(
         &mut self,
         id: Id,
         handler: impl Fn(P, &E) + Send + Sync + 'static,
-    ) -> (Id, any::TypeId)
+    ) -> HandlerId
     where
         T: Unpackable<'a, Unpacked = P>,
     {
-        let k = (id, any::TypeId::of::<E>());
-        self.handlers.insert(
-            k.clone(),
-            Arc::new(move |packed, ev| handler(T::unpack(packed), ev.downcast_ref::<E>().unwrap())),
+        self.on_with_priority(id, 0, handler)
+    }
+
+    /// Adds a handler with a given priority.
+    ///
+    /// Handlers with a higher `priority` are invoked first during [`dispatch`](Listener::dispatch). Handlers
+    /// of equal priority (including the default of `0` used by [`on`](Listener::on)) are invoked in the
+    /// order they were added. Returns a [`HandlerId`] which can be passed to [`remove`](Listener::remove)
+    /// to remove this specific handler.
+    pub fn on_with_priority<'a, E: Send + Sync + 'static, P: 'a>(
+        &mut self,
+        id: Id,
+        priority: i32,
+        handler: impl Fn(P, &E) + Send + Sync + 'static,
+    ) -> HandlerId
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_ctl_with_priority(id, priority, move |packed, ev| {
+            handler(packed, ev);
+            Propagation::Continue
+        })
+    }
+
+    /// Adds a handler to `self` and returns `Self`. Like [`and_on`](Listener::and_on), but the handler
+    /// returns [`Propagation`] to control whether lower-priority handlers still see the event.
+    pub fn and_on_ctl<'a, E: Send + Sync + 'static, P: 'a>(
+        mut self,
+        id: Id,
+        handler: impl Fn(P, &E) -> Propagation + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_ctl(id, handler);
+        self
+    }
+
+    /// Adds a handler to `self` and returns `Self`. Like [`and_on_with_priority`](Listener::and_on_with_priority),
+    /// but the handler returns [`Propagation`] to control whether lower-priority handlers still see the event.
+    pub fn and_on_ctl_with_priority<'a, E: Send + Sync + 'static, P: 'a>(
+        mut self,
+        id: Id,
+        priority: i32,
+        handler: impl Fn(P, &E) -> Propagation + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_ctl_with_priority(id, priority, handler);
+        self
+    }
+
+    /// Adds a handler whose return value controls event propagation.
+    ///
+    /// Like [`on`](Listener::on), but the handler returns [`Propagation::Stop`] to prevent the event
+    /// from reaching any remaining (lower-priority) handlers for this dispatch, or [`Propagation::Continue`]
+    /// to let it proceed as normal.
+    ///
+    /// Equivalent to [`on_ctl_with_priority`](Listener::on_ctl_with_priority) with a priority of `0`.
+    pub fn on_ctl<'a, E: Send + Sync + 'static, P: 'a>(
+        &mut self,
+        id: Id,
+        handler: impl Fn(P, &E) -> Propagation + Send + Sync + 'static,
+    ) -> HandlerId
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_ctl_with_priority(id, 0, handler)
+    }
+
+    /// Adds a handler with a given priority whose return value controls event propagation.
+    ///
+    /// See [`on_ctl`](Listener::on_ctl) and [`on_with_priority`](Listener::on_with_priority).
+    pub fn on_ctl_with_priority<'a, E: Send + Sync + 'static, P: 'a>(
+        &mut self,
+        id: Id,
+        priority: i32,
+        handler: impl Fn(P, &E) -> Propagation + Send + Sync + 'static,
+    ) -> HandlerId
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.insert_handler(
+            id,
+            any::TypeId::of::<E>(),
+            priority,
+            Arc::new(move |packed, ev| {
+                handler(
+                    unsafe { T::unpack(packed) },
+                    ev.downcast_ref::<E>().unwrap(),
+                )
+            }),
+        )
+    }
+
+    fn insert_handler(
+        &mut self,
+        id: Id,
+        type_id: any::TypeId,
+        priority: i32,
+        handler: Handler<T>,
+    ) -> HandlerId {
+        let handler_id = crate::id::next();
+        Self::insert_into(
+            self.handlers.entry((id, type_id)).or_default(),
+            priority,
+            handler_id,
+            handler,
         );
-        k
+        handler_id
     }
 
-    /// Removes a handler which matches a specific `id` and event type.
-    pub fn remove<E: 'static>(&mut self, id: Id) -> bool {
-        self.handlers
-            .remove(&(id, any::TypeId::of::<E>()))
-            .is_some()
+    /// Adds a handler to `self` and returns `Self`. Like [`on_any`](Listener::on_any), it fires for
+    /// every emitted event of type `E`, regardless of source ID.
+    pub fn and_on_any<'a, E: Send + Sync + 'static, P: 'a>(
+        mut self,
+        handler: impl Fn(P, &E) + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.on_any(handler);
+        self
     }
 
-    /// Returns `true` if there is a handler handling `id` and event type `E`.
+    /// Adds a handler which fires for every emitted event of type `E`, regardless of source ID, in
+    /// addition to any ID-specific handlers registered with [`on`](Listener::on).
+    ///
+    /// During [`dispatch`](Listener::dispatch), ID-specific handlers for a matched event run first
+    /// (in priority order), followed by type-wildcard handlers (also in priority order).
+    pub fn on_any<'a, E: Send + Sync + 'static, P: 'a>(
+        &mut self,
+        handler: impl Fn(P, &E) + Send + Sync + 'static,
+    ) -> HandlerId
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        let handler_id = crate::id::next();
+        Self::insert_into(
+            self.any_handlers.entry(any::TypeId::of::<E>()).or_default(),
+            0,
+            handler_id,
+            Arc::new(move |packed, ev| {
+                handler(
+                    unsafe { T::unpack(packed) },
+                    ev.downcast_ref::<E>().unwrap(),
+                );
+                Propagation::Continue
+            }),
+        );
+        handler_id
+    }
+
+    fn insert_into(
+        bucket: &mut HandlerBucket<T>,
+        priority: i32,
+        handler_id: HandlerId,
+        handler: Handler<T>,
+    ) {
+        // Insert after every existing handler of priority `>= priority` to keep the vec sorted
+        // in descending priority order while preserving insertion order for equal priorities.
+        let pos = bucket.partition_point(|(p, _, _)| *p >= priority);
+        bucket.insert(pos, (priority, handler_id, handler));
+    }
+
+    /// Removes a specific handler, identified by the [`HandlerId`] returned from [`on`](Listener::on),
+    /// which matches a specific `id` and event type.
+    pub fn remove<E: 'static>(&mut self, id: Id, handler_id: HandlerId) -> bool {
+        let k = (id, any::TypeId::of::<E>());
+        let Some(bucket) = self.handlers.get_mut(&k) else {
+            return false;
+        };
+
+        let found = bucket
+            .iter()
+            .position(|(_, hid, _)| *hid == handler_id)
+            .map(|pos| {
+                bucket.remove(pos);
+            })
+            .is_some();
+
+        if bucket.is_empty() {
+            self.handlers.remove(&k);
+        }
+
+        found
+    }
+
+    /// Removes a specific type-wildcard handler, identified by the [`HandlerId`] returned from
+    /// [`on_any`](Listener::on_any), which matches event type `E`.
+    pub fn remove_any<E: 'static>(&mut self, handler_id: HandlerId) -> bool {
+        let type_id = any::TypeId::of::<E>();
+        let Some(bucket) = self.any_handlers.get_mut(&type_id) else {
+            return false;
+        };
+
+        let found = bucket
+            .iter()
+            .position(|(_, hid, _)| *hid == handler_id)
+            .map(|pos| {
+                bucket.remove(pos);
+            })
+            .is_some();
+
+        if bucket.is_empty() {
+            self.any_handlers.remove(&type_id);
+        }
+
+        found
+    }
+
+    /// Returns `true` if there is at least one handler handling `id` and event type `E`.
     pub fn contains<E: 'static>(&self, id: Id) -> bool {
-        self.handlers.contains_key(&(id, any::TypeId::of::<E>()))
+        self.handlers
+            .get(&(id, any::TypeId::of::<E>()))
+            .is_some_and(|bucket| !bucket.is_empty())
     }
 
-    /// Processes incoming events and invokes the corresponding handler.
+    /// Processes incoming events and invokes the corresponding handlers, highest priority first.
     pub fn dispatch(&mut self, it: <T as Unpackable<'_>>::Unpacked)
     where
         T: for<'a> Unpackable<'a>,
     {
         let packed = T::pack(it);
         for event in self.listener.peek() {
-            if let Some(handler) = self.handlers.get_mut(&(event.id.clone(), event.type_id)) {
-                handler(packed, event.data.as_ref());
+            let mut stopped = false;
+
+            if let Some(handlers) = self.handlers.get_mut(&(event.id.clone(), event.type_id)) {
+                for (_, _, handler) in handlers.iter_mut() {
+                    if handler(packed, event.data.as_ref()) == Propagation::Stop {
+                        stopped = true;
+                        break;
+                    }
+                }
+            }
+
+            if !stopped {
+                if let Some(handlers) = self.any_handlers.get_mut(&event.type_id) {
+                    for (_, _, handler) in handlers.iter_mut() {
+                        if handler(packed, event.data.as_ref()) == Propagation::Stop {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<Id: Clone + core::hash::Hash + Eq, R: Sync + 'static> Listener<Id, Read<R>> {
+    /// Like [`dispatch`](Listener::dispatch), but fans independent events out across a bounded
+    /// pool of scoped worker threads (sized to [`std::thread::available_parallelism`], capped at
+    /// the number of events), joining all of them before returning.
+    ///
+    /// Because the context is read-only (`&R`), every matched handler only reads from it, so running
+    /// different events' handlers concurrently carries no aliasing hazard. Within a single event,
+    /// handlers still run serially in priority order on that event's worker, so [`Propagation::Stop`]
+    /// is honoured exactly as in [`dispatch`](Listener::dispatch) (lower-priority handlers for that
+    /// event are skipped); it just cannot affect events dispatched in parallel with it.
+    ///
+    /// Requires the `std` feature, since it is built on [`std::thread::scope`].
+    pub fn dispatch_parallel(&mut self, it: &R) {
+        self.dispatch_packed_parallel(Read::<R>::pack(it))
+    }
+
+    pub fn dispatch_packed_parallel(&mut self, packed: <Read<R> as Packable>::Packed) {
+        // `*const R` is not `Send` on its own, but every handler we hand it to only reads through
+        // it, and `R: Sync` guarantees concurrent reads from multiple threads are sound.
+        //
+        // `Clone`/`Copy` are implemented by hand rather than derived: `#[derive(Copy)]` would add
+        // an (unnecessary) `R: Copy` bound, which most `R` used with `dispatch_parallel` won't satisfy.
+        struct SendPtr<R>(*const R);
+        impl<R> Clone for SendPtr<R> {
+            fn clone(&self) -> Self {
+                *self
             }
         }
+        impl<R> Copy for SendPtr<R> {}
+        unsafe impl<R> Send for SendPtr<R> {}
+        let packed = SendPtr(packed);
+
+        // Resolve each event's handlers up front so the scoped workers below only need to read
+        // from this, rather than `self`.
+        let work: Vec<_> = self
+            .listener
+            .peek()
+            .into_iter()
+            .map(|event| {
+                let handlers = self
+                    .handlers
+                    .get(&(event.id.clone(), event.type_id))
+                    .cloned();
+                let any_handlers = self.any_handlers.get(&event.type_id).cloned();
+                (handlers, any_handlers, Arc::clone(&event.data))
+            })
+            .collect();
+
+        if work.is_empty() {
+            return;
+        }
+
+        // Split the events across a bounded worker pool instead of spawning a thread per event:
+        // `dispatch` is meant to be called with potentially many queued events, and an unbounded
+        // thread-per-event spawn would regress hard on a busy queue.
+        //
+        // A fixed `chunk_size = len.div_ceil(worker_count)` would leave workers idle whenever
+        // `worker_count` doesn't evenly divide `len` (e.g. 9 events over 8 workers would chunk
+        // into 5 chunks of size 2, using only 5 of the 8 workers). Instead, size each of the
+        // `worker_count` chunks off of what's left so the remainder spreads across every worker.
+        let worker_count = std::thread::available_parallelism()
+            .map(core::num::NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(work.len());
+        let mut remaining = work.as_slice();
+        let chunks = (0..worker_count).map(|i| {
+            let take = remaining.len().div_ceil(worker_count - i);
+            let (chunk, rest) = remaining.split_at(take);
+            remaining = rest;
+            chunk
+        });
+
+        std::thread::scope(|scope| {
+            for chunk in chunks {
+                scope.spawn(move || {
+                    // Binds the whole `SendPtr`, not just its `.0` field: Rust 2021's disjoint
+                    // closure capture would otherwise capture `packed.0: *const R` directly from
+                    // the `packed.0` uses below, sidestepping our `unsafe impl Send for SendPtr`.
+                    let packed = packed;
+
+                    for (handlers, any_handlers, data) in chunk {
+                        let mut stopped = false;
+
+                        if let Some(handlers) = handlers {
+                            for (_, _, handler) in handlers.iter() {
+                                if handler(packed.0, data.as_ref()) == Propagation::Stop {
+                                    stopped = true;
+                                    break;
+                                }
+                            }
+                        }
+
+                        if !stopped {
+                            if let Some(handlers) = any_handlers {
+                                for (_, _, handler) in handlers.iter() {
+                                    if handler(packed.0, data.as_ref()) == Propagation::Stop {
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                });
+            }
+        });
     }
 }
 
 /// Thread-safe heterogenous queue.
 ///
 /// In order to process events, specialized listeners need to be created via [`listen`](Queue::listen).
-pub struct Queue<Id: Clone + std::hash::Hash + Eq + 'static = u64> {
+pub struct Queue<Id: Clone + core::hash::Hash + Eq + 'static = u64> {
     q: event::ts::Queue<QueueEvent<Id>>,
 }
 
-impl<Id: Clone + std::hash::Hash + Eq + 'static> Default for Queue<Id> {
+impl<Id: Clone + core::hash::Hash + Eq + 'static> Default for Queue<Id> {
     fn default() -> Self {
         Queue {
             q: Default::default(),
@@ -102,7 +454,7 @@ impl<Id: Clone + std::hash::Hash + Eq + 'static> Default for Queue<Id> {
     }
 }
 
-impl<Id: Clone + std::hash::Hash + Eq + 'static> Queue<Id> {
+impl<Id: Clone + core::hash::Hash + Eq + 'static> Queue<Id> {
     /// Creates a new [`Queue`](Queue). Equivalent to `Queue::default()`.
     #[inline]
     pub fn new() -> Self {
@@ -140,6 +492,7 @@ impl<Id: Clone + std::hash::Hash + Eq + 'static> Queue<Id> {
     pub fn listen<T: Packable>(&self) -> EventListener<T, Id> {
         EventListener {
             handlers: Default::default(),
+            any_handlers: Default::default(),
             listener: self.q.listen(),
         }
     }
@@ -148,6 +501,118 @@ impl<Id: Clone + std::hash::Hash + Eq + 'static> Queue<Id> {
 /// Thread-safe listener associated with an [`Queue`](Queue).
 pub type EventListener<T, Id = u64> = Listener<Id, T>;
 
+/// A registered responder: takes the packed context and the boxed request, returns the boxed response.
+type Responder<T> = Box<dyn Fn(<T as Packable>::Packed, &dyn Any) -> Box<dyn Any> + Send + Sync>;
+
+/// A request/response ("ask") subsystem, built on the same [`Packable`]/[`Unpackable`] context
+/// machinery as [`Listener`].
+///
+/// Unlike [`Queue`], asking does not go through an event queue: [`ask`](Requester::ask)
+/// synchronously invokes the matching responder and returns its result directly to the caller.
+pub struct Requester<Id: Clone + core::hash::Hash + Eq, T: Packable> {
+    responders: HashMap<(Id, any::TypeId), Responder<T>>,
+}
+
+impl<Id: Clone + core::hash::Hash + Eq, T: Packable> Default for Requester<Id, T> {
+    fn default() -> Self {
+        Requester {
+            responders: Default::default(),
+        }
+    }
+}
+
+impl<Id: Clone + core::hash::Hash + Eq, T: Packable> Requester<Id, T> {
+    /// Creates a new, empty [`Requester`](Requester). Equivalent to `Requester::default()`.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Registers a responder for requests of type `Req` made under `id`, and returns `self`.
+    ///
+    /// If `id` and the request type are already being responded to, the responder is replaced.
+    pub fn and_respond<'a, Req: Send + Sync + 'static, Res: Send + Sync + 'static, P: 'a>(
+        mut self,
+        id: Id,
+        responder: impl Fn(P, &Req) -> Res + Send + Sync + 'static,
+    ) -> Self
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        self.respond(id, responder);
+        self
+    }
+
+    /// Registers a responder for requests of type `Req` made under `id`.
+    ///
+    /// If `id` and the request type are already being responded to, the responder is replaced.
+    pub fn respond<'a, Req: Send + Sync + 'static, Res: Send + Sync + 'static, P: 'a>(
+        &mut self,
+        id: Id,
+        responder: impl Fn(P, &Req) -> Res + Send + Sync + 'static,
+    ) -> (Id, any::TypeId)
+    where
+        T: Unpackable<'a, Unpacked = P>,
+    {
+        let k = (id, any::TypeId::of::<Req>());
+        self.responders.insert(
+            k.clone(),
+            Box::new(move |packed, req| {
+                Box::new(responder(
+                    unsafe { T::unpack(packed) },
+                    req.downcast_ref::<Req>().unwrap(),
+                )) as Box<dyn Any>
+            }),
+        );
+        k
+    }
+
+    /// Removes the responder which matches a specific `id` and request type.
+    pub fn remove<Req: 'static>(&mut self, id: Id) -> bool {
+        self.responders
+            .remove(&(id, any::TypeId::of::<Req>()))
+            .is_some()
+    }
+
+    /// Returns `true` if there is a responder for `id` and request type `Req`.
+    pub fn contains<Req: 'static>(&self, id: Id) -> bool {
+        self.responders
+            .contains_key(&(id, any::TypeId::of::<Req>()))
+    }
+
+    /// Asks `id` a request of type `Req`, synchronously invoking the matching responder and
+    /// returning its response, or `None` if no responder is registered for `id` and `Req`.
+    ///
+    /// Note that `None` is also returned if a responder *is* registered for `id` and `Req` but was
+    /// registered with a different `Res` than the one requested here — unlike [`dispatch`](Listener::dispatch),
+    /// which panics on a mismatched event type, a mismatched `Res` is indistinguishable from "no responder".
+    pub fn ask<Req: Send + Sync + 'static, Res: 'static>(
+        &self,
+        it: <T as Unpackable<'_>>::Unpacked,
+        id: Id,
+        req: Req,
+    ) -> Option<Res>
+    where
+        T: for<'a> Unpackable<'a>,
+    {
+        self.ask_packed(T::pack(it), id, req)
+    }
+
+    /// Packed form of [`ask`](Self::ask). See its docs for the `Res`-mismatch caveat.
+    pub fn ask_packed<Req: Send + Sync + 'static, Res: 'static>(
+        &self,
+        packed: <T as Packable>::Packed,
+        id: Id,
+        req: Req,
+    ) -> Option<Res> {
+        let responder = self.responders.get(&(id, any::TypeId::of::<Req>()))?;
+        responder(packed, &req as &dyn Any)
+            .downcast::<Res>()
+            .ok()
+            .map(|res| *res)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -191,6 +656,7 @@ mod tests {
         assert_eq!(&v1, &["b0", "b0"]);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_event_dispatch_threaded() {
         use std::sync::Mutex;
@@ -236,4 +702,127 @@ mod tests {
         assert_eq!(&v0, &["a1", "b0", "a0", "b0"]);
         assert_eq!(*v1.lock().unwrap(), &["b0", "b0"]);
     }
+
+    #[test]
+    fn test_priority_and_propagation() {
+        let queue: Queue = Queue::new();
+
+        let mut l0 = queue
+            .listen::<Write<Vec<&'static str>>>()
+            .and_on(0, |o, _: &EventA| {
+                o.push("default");
+            })
+            .and_on_with_priority(0, 10, |o, _: &EventA| {
+                o.push("high");
+            })
+            .and_on_with_priority(0, -10, |o, _: &EventA| {
+                o.push("low");
+            });
+
+        queue.emit(0, EventA);
+
+        let mut v0 = Vec::new();
+        l0.dispatch(&mut v0);
+        assert_eq!(&v0, &["high", "default", "low"]);
+
+        let mut l1 = queue.listen::<Write<Vec<&'static str>>>();
+        l1.on_ctl_with_priority(0, 10, |o: &mut Vec<&'static str>, _: &EventB| {
+            o.push("first");
+            Propagation::Stop
+        });
+        l1.on_with_priority(0, 0, |o, _: &EventB| {
+            o.push("second");
+        });
+
+        queue.emit(0, EventB);
+
+        let mut v1 = Vec::new();
+        l1.dispatch(&mut v1);
+        assert_eq!(&v1, &["first"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dispatch_parallel() {
+        use std::sync::Mutex;
+
+        let queue: Queue = Queue::new();
+
+        let mut l0 = queue
+            .listen::<Read<Mutex<Vec<&'static str>>>>()
+            .and_on(0, |o: &Mutex<Vec<&'static str>>, _: &EventA| {
+                o.lock().unwrap().push("a0");
+            })
+            .and_on(1, |o: &Mutex<Vec<&'static str>>, _: &EventA| {
+                o.lock().unwrap().push("a1");
+            });
+
+        queue.emit(0, EventA);
+        queue.emit(1, EventA);
+
+        let results = Mutex::new(Vec::new());
+        l0.dispatch_parallel(&results);
+
+        let mut results = results.into_inner().unwrap();
+        results.sort_unstable();
+        assert_eq!(&results, &["a0", "a1"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_dispatch_parallel_propagation() {
+        use std::sync::Mutex;
+
+        let queue: Queue = Queue::new();
+
+        let mut l0 = queue
+            .listen::<Read<Mutex<Vec<&'static str>>>>()
+            .and_on_ctl_with_priority(0, 1, |o: &Mutex<Vec<&'static str>>, _: &EventA| {
+                o.lock().unwrap().push("high");
+                Propagation::Stop
+            })
+            .and_on_ctl_with_priority(0, 0, |o: &Mutex<Vec<&'static str>>, _: &EventA| {
+                o.lock().unwrap().push("low");
+                Propagation::Continue
+            });
+
+        queue.emit(0, EventA);
+
+        let results = Mutex::new(Vec::new());
+        l0.dispatch_parallel(&results);
+
+        let results = results.into_inner().unwrap();
+        assert_eq!(&results, &["high"]);
+    }
+
+    #[test]
+    fn test_on_any() {
+        let queue: Queue = Queue::new();
+
+        let mut l0 = queue
+            .listen::<Write<Vec<&'static str>>>()
+            .and_on(0, |o, _: &EventA| {
+                o.push("id-specific");
+            })
+            .and_on_any(|o, _: &EventA| {
+                o.push("wildcard");
+            });
+
+        queue.emit(0, EventA);
+        queue.emit(1, EventA);
+
+        let mut v0 = Vec::new();
+        l0.dispatch(&mut v0);
+        assert_eq!(&v0, &["id-specific", "wildcard", "wildcard"]);
+    }
+
+    struct Ping;
+
+    #[test]
+    fn test_ask() {
+        let requester = Requester::<u64, ()>::new().and_respond(0, |_, _: &Ping| 42i32);
+
+        assert_eq!(requester.ask((), 0, Ping), Some(42i32));
+        assert_eq!(requester.ask::<Ping, i32>((), 1, Ping), None);
+    }
 }
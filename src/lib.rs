@@ -1,3 +1,9 @@
+//! `uniq` builds as `no_std` (plus `alloc`) when the `std` feature is off; `std` is enabled by default.
+//! Disable default features to build against `alloc` alone, e.g. for embedded/RTOS targets.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
 pub mod arc;
 pub mod rc;
 
@@ -5,11 +11,15 @@ pub(crate) mod pack;
 
 pub use pack::{Packable, Read, Unpackable, Write};
 
-use std::any;
+use core::any;
 
-#[cfg(feature = "id")]
 pub mod id;
 
+#[cfg(not(feature = "std"))]
+pub(crate) use hashbrown::HashMap;
+#[cfg(feature = "std")]
+pub(crate) use std::collections::HashMap;
+
 /// Simple event type which stores the event type ID, the source ID and the event data itself.
 #[derive(Debug, Clone)]
 pub struct Event<Id: Clone, Data: Clone + 'static> {
@@ -17,3 +27,21 @@ pub struct Event<Id: Clone, Data: Clone + 'static> {
     type_id: any::TypeId,
     data: Data,
 }
+
+/// Unique identifier for a single registered handler, returned by `on` (and the other non-chaining
+/// `on_*` methods) and accepted by `remove`/`remove_any`. The chaining `and_on*` methods discard it.
+///
+/// Generated from [`id::next`](id::next), so it is unique across the whole process, not just a single listener.
+pub type HandlerId = u64;
+
+/// Controls whether an event continues on to the next (lower-priority) handler during dispatch.
+///
+/// Returned from handlers registered with `on_ctl`/`and_on_ctl`. Handlers registered with the plain
+/// `on`/`and_on` family always behave as if they returned [`Propagation::Continue`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Propagation {
+    /// Allow the event to reach the next handler.
+    Continue,
+    /// Prevent the event from reaching any remaining handlers for this dispatch.
+    Stop,
+}
@@ -1,4 +1,4 @@
-use std::marker::PhantomData;
+use core::marker::PhantomData;
 
 /// Marker denoting an immutable parameter which will appear in the `Listener` method signatures and handlers as `&T`.
 pub struct Read<T: ?Sized + 'static>(PhantomData<T>);
@@ -26,6 +26,12 @@ pub trait Unpackable<'a>: Packable {
     /// Packs an unpacked reference into a packed non-reference.
     fn pack(unpacked: Self::Unpacked) -> Self::Packed;
     /// Unpacks an non-reference type into an unpacked reference.
+    ///
+    /// # Safety
+    ///
+    /// `packed` must have been produced by [`pack`](Unpackable::pack) from a reference that is
+    /// still valid (not dangling, not aliased in violation of `&`/`&mut` rules) for the `'a`
+    /// the caller unpacks into.
     unsafe fn unpack(packed: Self::Packed) -> Self::Unpacked;
 }
 
@@ -0,0 +1,18 @@
+//! Vendored replacement for the (unpublished) `reclutch_event` crate `uniq` is written against.
+//!
+//! Only the surface `uniq` actually calls is implemented: a broadcast-style event log where each
+//! listener tracks its own read position and [`peek`](ts::Listener::peek)/[`peek`](RcEventListener::peek)
+//! returns (and consumes) every event appended since the listener's last peek. `ts` is the
+//! thread-safe (spinlock-guarded) variant; the crate root holds the single-threaded, `Rc`-based one.
+#![no_std]
+
+extern crate alloc;
+
+pub mod ts;
+
+mod rc_queue;
+pub use rc_queue::{RcEventListener, RcEventQueue};
+
+/// Empty on purpose: kept for forwards-compatibility with the real crate's API. `uniq` does not
+/// import it and doesn't rely on any trait it would bring into scope.
+pub mod prelude {}
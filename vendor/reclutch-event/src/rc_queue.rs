@@ -0,0 +1,67 @@
+//! Single-threaded, `Rc`-based counterpart to [`ts`](crate::ts).
+
+use {alloc::rc::Rc, alloc::vec::Vec, core::cell::RefCell, core::fmt};
+
+struct Inner<E> {
+    events: RefCell<Vec<E>>,
+}
+
+/// A single-threaded, append-only event log. Events are never removed from it directly; each
+/// [`RcEventListener`] created via [`listen`](RcEventQueue::listen) tracks its own read position
+/// independently.
+pub struct RcEventQueue<E: Clone> {
+    inner: Rc<Inner<E>>,
+}
+
+impl<E: Clone> Default for RcEventQueue<E> {
+    fn default() -> Self {
+        RcEventQueue {
+            inner: Rc::new(Inner {
+                events: RefCell::new(Vec::new()),
+            }),
+        }
+    }
+}
+
+impl<E: Clone> RcEventQueue<E> {
+    /// Appends an event to the queue. Visible to every [`RcEventListener`] on its next
+    /// [`peek`](RcEventListener::peek).
+    pub fn emit_owned(&self, event: E) {
+        self.inner.events.borrow_mut().push(event);
+    }
+
+    /// Creates a listener which will see every event emitted from this point onward.
+    pub fn listen(&self) -> RcEventListener<E> {
+        RcEventListener {
+            inner: Rc::clone(&self.inner),
+            read: self.inner.events.borrow().len(),
+        }
+    }
+}
+
+// Not derived: that would require `E: Debug`, but `uniq::rc::Queue` derives `Debug` without
+// requiring its event payload to be one.
+impl<E: Clone> fmt::Debug for RcEventQueue<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RcEventQueue")
+            .field("len", &self.inner.events.borrow().len())
+            .finish()
+    }
+}
+
+/// A listener over an [`RcEventQueue`], created via [`RcEventQueue::listen`].
+pub struct RcEventListener<E: Clone> {
+    inner: Rc<Inner<E>>,
+    read: usize,
+}
+
+impl<E: Clone> RcEventListener<E> {
+    /// Returns every event emitted on the underlying [`RcEventQueue`] since this listener's last `peek`.
+    pub fn peek(&mut self) -> Vec<E> {
+        let events = self.inner.events.borrow();
+        let new_events = events[self.read..].to_vec();
+        drop(events);
+        self.read = self.inner.events.borrow().len();
+        new_events
+    }
+}
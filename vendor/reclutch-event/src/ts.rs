@@ -0,0 +1,84 @@
+//! Thread-safe event queue/listener, guarded by a spinlock since this crate is `no_std`.
+
+use {
+    alloc::sync::Arc,
+    alloc::vec::Vec,
+    core::cell::UnsafeCell,
+    core::sync::atomic::{AtomicBool, Ordering},
+};
+
+struct Inner<E> {
+    locked: AtomicBool,
+    events: UnsafeCell<Vec<E>>,
+}
+
+// SAFETY: `events` is only ever touched while `locked` has been acquired via `with_events`, which
+// gives exclusive access regardless of which thread is calling; `E: Send` is what actually lets
+// values cross threads.
+unsafe impl<E: Send> Send for Inner<E> {}
+unsafe impl<E: Send> Sync for Inner<E> {}
+
+impl<E> Inner<E> {
+    fn with_events<R>(&self, f: impl FnOnce(&mut Vec<E>) -> R) -> R {
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+        // SAFETY: the spinlock above guarantees exclusive access for the duration of `f`.
+        let result = f(unsafe { &mut *self.events.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+}
+
+/// A thread-safe, append-only event log. Events are never removed from it directly; each
+/// [`Listener`] created via [`listen`](Queue::listen) tracks its own read position independently.
+pub struct Queue<E: Clone> {
+    inner: Arc<Inner<E>>,
+}
+
+impl<E: Clone> Default for Queue<E> {
+    fn default() -> Self {
+        Queue {
+            inner: Arc::new(Inner {
+                locked: AtomicBool::new(false),
+                events: UnsafeCell::new(Vec::new()),
+            }),
+        }
+    }
+}
+
+impl<E: Clone> Queue<E> {
+    /// Appends an event to the queue. Visible to every [`Listener`] on its next [`peek`](Listener::peek).
+    pub fn emit_owned(&self, event: E) {
+        self.inner.with_events(|events| events.push(event));
+    }
+
+    /// Creates a listener which will see every event emitted from this point onward.
+    pub fn listen(&self) -> Listener<E> {
+        Listener {
+            inner: Arc::clone(&self.inner),
+            read: self.inner.with_events(|events| events.len()),
+        }
+    }
+}
+
+/// A listener over a [`Queue`], created via [`Queue::listen`].
+pub struct Listener<E: Clone> {
+    inner: Arc<Inner<E>>,
+    read: usize,
+}
+
+impl<E: Clone> Listener<E> {
+    /// Returns every event emitted on the underlying [`Queue`] since this listener's last `peek`.
+    pub fn peek(&mut self) -> Vec<E> {
+        let (new_events, read) = self
+            .inner
+            .with_events(|events| (events[self.read..].to_vec(), events.len()));
+        self.read = read;
+        new_events
+    }
+}